@@ -0,0 +1,68 @@
+//! Stress-tests `Router` under concurrent access from many worker threads,
+//! asserting that no accelerator exceeds its capacity, both right after each
+//! successful route and once all threads have finished. The per-thread
+//! success counts are summed and printed; that total is not checked against
+//! any other count.
+use intelligent_routing::accelerator::Accelerator;
+use intelligent_routing::request::Request;
+use intelligent_routing::router::Router;
+use intelligent_routing::strategies::p2c::PowerOfTwoChoices;
+use rand::Rng;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::thread;
+
+fn main() {
+    let num_accelerators = 64;
+    let capacity = 50;
+    let num_threads = 8;
+    let requests_per_thread = 5_000;
+
+    let strategy = Box::new(PowerOfTwoChoices::new());
+    let mut router = Router::new(strategy);
+    for i in 0..num_accelerators {
+        router.add_accelerator(Accelerator::new(i, capacity));
+    }
+    let router = Arc::new(router);
+
+    let handles: Vec<_> = (0..num_threads)
+        .map(|thread_id| {
+            let router = Arc::clone(&router);
+            thread::spawn(move || {
+                let mut rng = rand::thread_rng();
+                let mut successes = 0usize;
+                for i in 0..requests_per_thread {
+                    let cost = rng.random_range(1..5);
+                    let request = Request::new(thread_id * requests_per_thread + i, cost, 1);
+                    if let Some(lease) = router.route_request(&request) {
+                        successes += 1;
+                        let acc_id = lease.accelerator_id();
+                        let load = router.accelerators[acc_id].current_load.load(Ordering::Relaxed);
+                        assert!(load <= capacity, "accelerator {acc_id} exceeded capacity: {load} > {capacity}");
+                        // Released immediately so load reflects only this request's window.
+                        drop(lease);
+                    }
+                }
+                successes
+            })
+        })
+        .collect();
+
+    let total_successes: usize = handles.into_iter().map(|h| h.join().unwrap()).sum();
+
+    for acc in &router.accelerators {
+        let load = acc.current_load.load(Ordering::Relaxed);
+        assert!(
+            load <= acc.capacity,
+            "accelerator {} exceeded capacity: {} > {}",
+            acc.id,
+            load,
+            acc.capacity
+        );
+    }
+
+    println!("Concurrent stress test complete!");
+    println!("Threads: {num_threads}, requests per thread: {requests_per_thread}");
+    println!("Total successful routes: {total_successes}");
+    println!("No accelerator exceeded capacity.");
+}
@@ -1,9 +1,111 @@
-#[derive(Debug, Clone)]
+use std::sync::atomic::{AtomicU32, AtomicU8, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Cold-start RTT penalty (in the same units as reported RTTs) assumed for
+/// an accelerator that has not yet completed a request under `PeakEwma`, so
+/// a freshly added accelerator isn't instantly flooded with traffic.
+pub const DEFAULT_COLD_START_RTT: f64 = 1_000.0;
+
+/// Default peak-EWMA decay time constant, in seconds (`report_rtt` divides
+/// the seconds elapsed since the previous report by it), consulted by
+/// `Router::report_rtt`. Configurable per-`Router` via
+/// `Router::with_ewma_tau` (and `SimConfig::ewma_tau`/`PyRouter`'s
+/// constructor, which thread through to it).
+pub const DEFAULT_EWMA_TAU: f64 = 10.0;
+
+/// Which scalar load signal a strategy should use when comparing accelerators.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LoadMetric {
+    /// Raw in-flight request count (`current_load`).
+    InFlight,
+    /// Latency-aware peak-EWMA estimate, modeled on tower-load's `PeakEwma`.
+    PeakEwma,
+}
+
+/// Circuit-breaker state for an accelerator's health.
+///
+/// Transitions: `Healthy` -> `Unhealthy` after enough consecutive reported
+/// failures, `Unhealthy` -> `Probing` once a cooldown elapses, and
+/// `Probing` -> `Healthy` on the first reported success (or back to
+/// `Unhealthy` on a failure).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum CircuitState {
+    Healthy = 0,
+    Unhealthy = 1,
+    Probing = 2,
+}
+
+impl CircuitState {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            0 => CircuitState::Healthy,
+            1 => CircuitState::Unhealthy,
+            _ => CircuitState::Probing,
+        }
+    }
+}
+
+/// Failure-streak bookkeeping for the circuit breaker. Guarded by a mutex
+/// since it's only touched on the rare failure/success/cooldown path, never
+/// on the hot routing path.
+#[derive(Debug)]
+struct FailureTracking {
+    consecutive_failures: u32,
+    unhealthy_since: Option<Instant>,
+}
+
+/// Peak-EWMA latency state. Guarded by a mutex alongside `FailureTracking`'s:
+/// `report_rtt` writes it off the hot routing path (on release/completion, not
+/// on every `route_request`), so a mutex is simpler than bit-cast atomics and
+/// keeps `ewma_rtt`/`last_update` updated together rather than racing.
+/// `load(LoadMetric::PeakEwma)` also takes this mutex, briefly, to read the
+/// estimate each time a strategy compares this accelerator.
+#[derive(Debug)]
+struct EwmaState {
+    ewma_rtt: f64,
+    last_update: Instant,
+}
+
+/// An accelerator's load counters are atomics so `Router` can route
+/// concurrently from many threads without a lock on the common path; the
+/// health-transition and RTT bookkeeping use a mutex, which routing also
+/// takes briefly when a strategy reads `load(LoadMetric::PeakEwma)`.
+#[derive(Debug)]
 pub struct Accelerator {
     pub id: usize,
     pub capacity: u32,
-    pub current_load: u32,
-    pub health_status: bool,
+    pub current_load: AtomicU32,
+    circuit_state: AtomicU8,
+    pub weight: f64,
+    ewma: Mutex<EwmaState>,
+    /// Number of requests currently in flight, tracked alongside `current_load`.
+    pub pending: AtomicU32,
+    failure_tracking: Mutex<FailureTracking>,
+}
+
+impl Clone for Accelerator {
+    fn clone(&self) -> Self {
+        let tracking = self.failure_tracking.lock().unwrap();
+        let ewma = self.ewma.lock().unwrap();
+        Self {
+            id: self.id,
+            capacity: self.capacity,
+            current_load: AtomicU32::new(self.current_load.load(Ordering::Relaxed)),
+            circuit_state: AtomicU8::new(self.circuit_state.load(Ordering::Relaxed)),
+            weight: self.weight,
+            ewma: Mutex::new(EwmaState {
+                ewma_rtt: ewma.ewma_rtt,
+                last_update: ewma.last_update,
+            }),
+            pending: AtomicU32::new(self.pending.load(Ordering::Relaxed)),
+            failure_tracking: Mutex::new(FailureTracking {
+                consecutive_failures: tracking.consecutive_failures,
+                unhealthy_since: tracking.unhealthy_since,
+            }),
+        }
+    }
 }
 
 impl Accelerator {
@@ -11,32 +113,195 @@ impl Accelerator {
         Self {
             id,
             capacity,
-            current_load: 0,
-            health_status: true,
+            current_load: AtomicU32::new(0),
+            circuit_state: AtomicU8::new(CircuitState::Healthy as u8),
+            weight: 1.0,
+            ewma: Mutex::new(EwmaState {
+                ewma_rtt: DEFAULT_COLD_START_RTT,
+                last_update: Instant::now(),
+            }),
+            pending: AtomicU32::new(0),
+            failure_tracking: Mutex::new(FailureTracking {
+                consecutive_failures: 0,
+                unhealthy_since: None,
+            }),
         }
     }
 
-    pub fn update_load(&mut self, load: u32) {
-        self.current_load = load;
+    /// Sets the routing weight consulted by weight-aware strategies such as
+    /// `WeightedP2C` and `WeightedRoundRobin`. A weight of `2.0` receives
+    /// roughly twice the traffic of the default `1.0`; a weight near `0.0`
+    /// is effectively drained without removing the accelerator from the pool.
+    pub fn with_weight(mut self, weight: f64) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    /// Seeds the initial peak-EWMA RTT estimate, overriding the cold-start
+    /// penalty applied by `new`. Useful when an accelerator's typical
+    /// latency is already known at construction time.
+    pub fn with_initial_rtt(mut self, rtt: f64) -> Self {
+        self.ewma.get_mut().unwrap().ewma_rtt = rtt;
+        self
+    }
+
+    pub fn circuit_state(&self) -> CircuitState {
+        CircuitState::from_u8(self.circuit_state.load(Ordering::Relaxed))
+    }
+
+    pub fn update_load(&self, load: u32) {
+        self.current_load.store(load, Ordering::Release);
     }
 
     pub fn is_available(&self) -> bool {
-        self.health_status && self.current_load < self.capacity
+        self.circuit_state() != CircuitState::Unhealthy
+            && self.current_load.load(Ordering::Relaxed) < self.capacity
     }
-    
-    pub fn add_load(&mut self, load: u32) -> Result<(), String> {
-        if self.current_load + load > self.capacity {
-            return Err("Capacity exceeded".to_string());
+
+    /// Records a failed request, tripping the circuit breaker to `Unhealthy`
+    /// once `failure_threshold` consecutive failures have been seen.
+    pub fn record_failure(&self, failure_threshold: u32) {
+        let mut tracking = self.failure_tracking.lock().unwrap();
+        tracking.consecutive_failures += 1;
+        if self.circuit_state() != CircuitState::Unhealthy && tracking.consecutive_failures >= failure_threshold {
+            self.circuit_state.store(CircuitState::Unhealthy as u8, Ordering::Relaxed);
+            tracking.unhealthy_since = Some(Instant::now());
+        }
+    }
+
+    /// Records a successful request, resetting the failure streak and
+    /// closing the circuit if it was in `Probing`.
+    pub fn record_success(&self) {
+        let mut tracking = self.failure_tracking.lock().unwrap();
+        tracking.consecutive_failures = 0;
+        if self.circuit_state() == CircuitState::Probing {
+            self.circuit_state.store(CircuitState::Healthy as u8, Ordering::Relaxed);
+            tracking.unhealthy_since = None;
+        }
+    }
+
+    /// Moves an `Unhealthy` accelerator into `Probing` once `cooldown` has
+    /// elapsed since it tripped, so the router can send it test traffic
+    /// again without waiting for a manual re-admission.
+    pub fn maybe_begin_probing(&self, now: Instant, cooldown: Duration) {
+        if self.circuit_state() == CircuitState::Unhealthy {
+            let tracking = self.failure_tracking.lock().unwrap();
+            if let Some(since) = tracking.unhealthy_since {
+                if now.duration_since(since) >= cooldown {
+                    self.circuit_state.store(CircuitState::Probing as u8, Ordering::Relaxed);
+                }
+            }
+        }
+    }
+
+    /// Commits `load` to the accelerator using a compare-and-swap loop, so
+    /// many threads can call this concurrently with no lock on the common path.
+    pub fn add_load(&self, load: u32) -> Result<(), String> {
+        loop {
+            let current = self.current_load.load(Ordering::Acquire);
+            let new_load = current.checked_add(load).filter(|&v| v <= self.capacity);
+            let Some(new_load) = new_load else {
+                return Err("Capacity exceeded".to_string());
+            };
+            if self
+                .current_load
+                .compare_exchange_weak(current, new_load, Ordering::AcqRel, Ordering::Acquire)
+                .is_ok()
+            {
+                self.pending.fetch_add(1, Ordering::AcqRel);
+                return Ok(());
+            }
+        }
+    }
+
+    pub fn remove_load(&self, load: u32) {
+        loop {
+            let current = self.current_load.load(Ordering::Acquire);
+            let new_load = current.saturating_sub(load);
+            if self
+                .current_load
+                .compare_exchange_weak(current, new_load, Ordering::AcqRel, Ordering::Acquire)
+                .is_ok()
+            {
+                break;
+            }
         }
-        self.current_load += load;
-        Ok(())
+        let _ = self
+            .pending
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |p| Some(p.saturating_sub(1)));
     }
-    
-    pub fn remove_load(&mut self, load: u32) {
-        if load > self.current_load {
-            self.current_load = 0;
+
+    /// Records an observed round-trip time and decays the peak-EWMA estimate.
+    ///
+    /// Follows tower-load's `PeakEwma`: an `rtt` above the current estimate
+    /// replaces it outright (the peak); otherwise the estimate decays toward
+    /// it as `ewma = rtt + (ewma - rtt) * exp(-elapsed/tau)`, which never lets
+    /// the decayed estimate drop faster than real observations arrive, so a
+    /// single fast response right after a slow one doesn't immediately erase
+    /// the slowness.
+    ///
+    /// Non-finite `rtt` (`NaN`/`inf`, e.g. from a caller's `0.0/0.0` timing
+    /// bug) is ignored rather than folded in, since it would otherwise
+    /// permanently poison `ewma_rtt` and panic the next `PeakEwma` comparison
+    /// against this accelerator. A non-finite or non-positive `tau` is
+    /// ignored for the same reason.
+    pub fn report_rtt(&self, rtt: f64, tau: f64, now: Instant) {
+        if !rtt.is_finite() || !tau.is_finite() || tau <= 0.0 {
+            return;
+        }
+        let mut ewma = self.ewma.lock().unwrap();
+        if rtt > ewma.ewma_rtt {
+            ewma.ewma_rtt = rtt;
         } else {
-            self.current_load -= load;
+            let elapsed = now.duration_since(ewma.last_update).as_secs_f64();
+            let decay = (-elapsed / tau).exp();
+            ewma.ewma_rtt = rtt + (ewma.ewma_rtt - rtt) * decay;
         }
+        ewma.last_update = now;
+    }
+
+    /// Returns the scalar load strategies should minimize, under the given metric.
+    pub fn load(&self, metric: LoadMetric) -> f64 {
+        match metric {
+            LoadMetric::InFlight => self.current_load.load(Ordering::Relaxed) as f64,
+            LoadMetric::PeakEwma => {
+                let ewma_rtt = self.ewma.lock().unwrap().ewma_rtt;
+                (self.pending.load(Ordering::Relaxed) as f64 + 1.0) * ewma_rtt
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_rtt_decays_toward_new_observation() {
+        let acc = Accelerator::new(0, 10).with_initial_rtt(100.0);
+        let now = Instant::now() + Duration::from_secs(100);
+        acc.report_rtt(10.0, 1.0, now);
+        let estimate = acc.load(LoadMetric::PeakEwma);
+        assert!(
+            (estimate - 10.0).abs() < 1e-6,
+            "expected the estimate to have decayed to ~10.0 after 100 taus, got {estimate}"
+        );
+    }
+
+    #[test]
+    fn report_rtt_ignores_non_finite_rtt() {
+        let acc = Accelerator::new(0, 10).with_initial_rtt(50.0);
+        acc.report_rtt(f64::NAN, DEFAULT_EWMA_TAU, Instant::now());
+        acc.report_rtt(f64::INFINITY, DEFAULT_EWMA_TAU, Instant::now());
+        assert_eq!(acc.load(LoadMetric::PeakEwma), 50.0, "a non-finite report must not poison ewma_rtt");
+    }
+
+    #[test]
+    fn report_rtt_ignores_non_finite_or_non_positive_tau() {
+        let acc = Accelerator::new(0, 10).with_initial_rtt(50.0);
+        acc.report_rtt(10.0, f64::NAN, Instant::now());
+        acc.report_rtt(10.0, 0.0, Instant::now());
+        acc.report_rtt(10.0, -1.0, Instant::now());
+        assert_eq!(acc.load(LoadMetric::PeakEwma), 50.0);
     }
 }
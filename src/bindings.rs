@@ -1,8 +1,10 @@
 use pyo3::prelude::*;
+use pyo3::types::PyAny;
 use crate::accelerator::Accelerator;
 use crate::request::Request;
-use crate::router::Router;
+use crate::router::{RequestLease, Router};
 use crate::strategies::{least_connections::LeastConnections, p2c::PowerOfTwoChoices, round_robin::RoundRobin};
+use std::time::Instant;
 
 #[pyclass(name = "Accelerator")]
 #[derive(Clone)]
@@ -31,7 +33,7 @@ impl PyAccelerator {
 
     #[getter]
     pub fn current_load(&self) -> u32 {
-        self.inner.current_load
+        self.inner.current_load.load(std::sync::atomic::Ordering::Relaxed)
     }
 }
 
@@ -61,6 +63,46 @@ impl PyRequest {
     }
 }
 
+/// A deterministic, Python-side handle for the load committed by a routed
+/// request. Release it explicitly with `release()` or use it as a context
+/// manager (`with router.route_request(req) as lease:`); either drops the
+/// inner Rust `RequestLease`, returning its cost to the accelerator.
+#[pyclass(name = "RequestLease")]
+pub struct PyRequestLease {
+    inner: Option<RequestLease>,
+}
+
+#[pymethods]
+impl PyRequestLease {
+    #[getter]
+    pub fn accelerator_id(&self) -> Option<usize> {
+        self.inner.as_ref().map(RequestLease::accelerator_id)
+    }
+
+    #[getter]
+    pub fn cost(&self) -> Option<u32> {
+        self.inner.as_ref().map(RequestLease::cost)
+    }
+
+    pub fn release(&mut self) {
+        self.inner.take();
+    }
+
+    pub fn __enter__(slf: PyRefMut<'_, Self>) -> PyRefMut<'_, Self> {
+        slf
+    }
+
+    #[pyo3(signature = (_exc_type=None, _exc_value=None, _traceback=None))]
+    pub fn __exit__(
+        &mut self,
+        _exc_type: Option<Bound<'_, PyAny>>,
+        _exc_value: Option<Bound<'_, PyAny>>,
+        _traceback: Option<Bound<'_, PyAny>>,
+    ) {
+        self.inner.take();
+    }
+}
+
 #[pyclass(name = "Router")]
 pub struct PyRouter {
     inner: Router,
@@ -68,8 +110,11 @@ pub struct PyRouter {
 
 #[pymethods]
 impl PyRouter {
+    /// `ewma_tau`, if given, overrides the peak-EWMA decay time constant
+    /// consulted by `report_rtt` (see `Router::with_ewma_tau`).
     #[new]
-    pub fn new(strategy_name: &str) -> PyResult<Self> {
+    #[pyo3(signature = (strategy_name, ewma_tau=None))]
+    pub fn new(strategy_name: &str, ewma_tau: Option<f64>) -> PyResult<Self> {
         let strategy: Box<dyn crate::router::LoadBalancingStrategy> = match strategy_name {
             "round_robin" => Box::new(RoundRobin::new()),
             "least_connections" => Box::new(LeastConnections::new()),
@@ -77,17 +122,52 @@ impl PyRouter {
             _ => return Err(pyo3::exceptions::PyValueError::new_err("Unknown strategy")),
         };
 
-        Ok(PyRouter {
-            inner: Router::new(strategy),
-        })
+        let mut inner = Router::new(strategy);
+        if let Some(tau) = ewma_tau {
+            inner = inner.with_ewma_tau(tau);
+        }
+
+        Ok(PyRouter { inner })
     }
 
     pub fn add_accelerator(&mut self, accelerator: &PyAccelerator) {
         self.inner.add_accelerator(accelerator.inner.clone());
     }
 
-    pub fn route_request(&mut self, request: &PyRequest) -> Option<usize> {
-        self.inner.route_request(&request.inner)
+    pub fn route_request(&mut self, request: &PyRequest) -> Option<PyRequestLease> {
+        self.inner
+            .route_request(&request.inner)
+            .map(|lease| PyRequestLease { inner: Some(lease) })
+    }
+
+    /// Ranks up to `n` distinct available accelerator ids for `request`,
+    /// best candidate first, for hedging or retrying without committing load.
+    pub fn route_n(&self, request: &PyRequest, n: usize) -> Vec<usize> {
+        self.inner.route_n(&request.inner, n)
+    }
+
+    /// Reports a failed request against an accelerator id, driving its
+    /// circuit breaker towards `Unhealthy` after enough consecutive failures.
+    pub fn report_failure(&mut self, acc_id: usize) {
+        self.inner.report_failure(acc_id);
+    }
+
+    /// Reports a successful request against an accelerator id, closing the
+    /// circuit if it was probing.
+    pub fn report_success(&mut self, acc_id: usize) {
+        self.inner.report_success(acc_id);
+    }
+
+    /// Drives cooldown-based re-admission of unhealthy accelerators. Should
+    /// be called periodically, e.g. from a background timer in the host application.
+    pub fn tick(&mut self) {
+        self.inner.tick(Instant::now());
+    }
+
+    /// Reports an observed round-trip time (in seconds) for an accelerator
+    /// id, feeding its peak-EWMA latency estimate.
+    pub fn report_rtt(&mut self, acc_id: usize, rtt: f64) {
+        self.inner.report_rtt(acc_id, rtt, Instant::now());
     }
 }
 
@@ -95,6 +175,7 @@ impl PyRouter {
 fn intelligent_routing(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<PyAccelerator>()?;
     m.add_class::<PyRequest>()?;
+    m.add_class::<PyRequestLease>()?;
     m.add_class::<PyRouter>()?;
     Ok(())
 }
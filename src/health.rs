@@ -0,0 +1,42 @@
+use std::time::Duration;
+
+/// Policy controlling the circuit-breaker state machine driven by
+/// `Router::report_failure` / `Router::report_success` / `Router::tick`.
+pub trait HealthChecker: Send + Sync {
+    /// Number of consecutive failures before an accelerator is marked `Unhealthy`.
+    fn failure_threshold(&self) -> u32;
+    /// How long an `Unhealthy` accelerator waits before being re-admitted as `Probing`.
+    fn cooldown(&self) -> Duration;
+}
+
+/// Default `HealthChecker`: trips after a fixed number of consecutive
+/// failures and re-admits after a fixed cooldown.
+pub struct ConsecutiveFailuresHealthChecker {
+    failure_threshold: u32,
+    cooldown: Duration,
+}
+
+impl ConsecutiveFailuresHealthChecker {
+    pub fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            failure_threshold,
+            cooldown,
+        }
+    }
+}
+
+impl Default for ConsecutiveFailuresHealthChecker {
+    fn default() -> Self {
+        Self::new(3, Duration::from_secs(30))
+    }
+}
+
+impl HealthChecker for ConsecutiveFailuresHealthChecker {
+    fn failure_threshold(&self) -> u32 {
+        self.failure_threshold
+    }
+
+    fn cooldown(&self) -> Duration {
+        self.cooldown
+    }
+}
@@ -1,6 +1,8 @@
 pub mod accelerator;
+pub mod health;
 pub mod request;
 pub mod router;
+pub mod sim;
 pub mod strategies;
 
 #[cfg(feature = "python")]
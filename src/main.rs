@@ -1,83 +1,51 @@
-use intelligent_routing::accelerator::Accelerator;
-use intelligent_routing::request::Request;
-use intelligent_routing::router::Router;
-use intelligent_routing::strategies::{least_connections::LeastConnections, p2c::PowerOfTwoChoices, round_robin::RoundRobin};
-use rand::Rng;
-use std::time::Instant;
-
-fn main() {
-    println!("Starting Intelligent Routing Simulation...");
-
-    // 1. Setup Accelerators
-    let num_accelerators = 10000;
-    let mut accelerators = Vec::with_capacity(num_accelerators);
-    for i in 0..num_accelerators {
-        accelerators.push(Accelerator::new(i, 100)); // Capacity 100
+use intelligent_routing::accelerator::DEFAULT_EWMA_TAU;
+use intelligent_routing::sim::{ArrivalProcess, CostDistribution, SimConfig};
+
+/// The scenario run when no config file is given on the command line.
+fn default_config() -> SimConfig {
+    SimConfig {
+        num_accelerators: 10_000,
+        accelerator_capacity: 100,
+        accelerator_weight: 1.0,
+        strategy: "p2c".to_string(),
+        ewma_tau: DEFAULT_EWMA_TAU,
+        arrival: ArrivalProcess::Fixed { rate_per_sec: 100_000.0 },
+        cost_distribution: CostDistribution::Uniform { min: 1, max: 9 },
+        duration_secs: 1.0,
     }
+}
 
-    // 2. Setup Router with a Strategy
-    // let strategy = Box::new(RoundRobin::new());
-    // let strategy = Box::new(LeastConnections::new());
-    let strategy = Box::new(PowerOfTwoChoices::new());
-    
-    let mut router = Router::new(strategy);
-    for acc in accelerators {
-        router.add_accelerator(acc);
+/// Loads a `SimConfig` from the path given as the first CLI argument
+/// (`.toml` or `.json`, by extension), or falls back to `default_config`
+/// so `intelligent_routing path/to/scenario.toml` sweeps a parameter grid
+/// without editing and recompiling this file.
+fn load_config() -> SimConfig {
+    let Some(path) = std::env::args().nth(1) else {
+        return default_config();
+    };
+
+    let contents = std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("failed to read {path}: {e}"));
+    match path.rsplit('.').next() {
+        Some("json") => SimConfig::from_json(&contents),
+        _ => SimConfig::from_toml(&contents),
     }
+    .unwrap_or_else(|e| panic!("failed to parse {path}: {e}"))
+}
 
-    // 3. Generate Requests
-    let num_requests = 100000;
-    let mut rng = rand::thread_rng();
-    
-    let start_time = Instant::now();
-    let mut success_count = 0;
-    let mut fail_count = 0;
+fn main() {
+    println!("Starting Intelligent Routing Simulation...");
 
-    for i in 0..num_requests {
-        let cost = rng.random_range(1..10);
-        let req = Request::new(i, cost, 1);
-        
-        match router.route_request(&req) {
-            Some(_acc_id) => {
-                // println!("Request {} routed to Accelerator {}", i, acc_id);
-                success_count += 1;
-            }
-            None => {
-                // println!("Request {} failed to route", i);
-                fail_count += 1;
-            }
-        }
-        
-        // Simulate load decay occasionally to free up space
-        if i % 100 == 0 {
-             for acc in &mut router.accelerators {
-                 acc.remove_load(5); // Decay load
-             }
-        }
-    }
+    let config = load_config();
 
-    let duration = start_time.elapsed();
+    let stats = intelligent_routing::sim::run_experiment(&config).expect("valid SimConfig");
 
     println!("Simulation Complete!");
-    println!("Time elapsed: {:?}", duration);
-    println!("Total Requests: {}", num_requests);
-    println!("Successful Routes: {}", success_count);
-    println!("Failed Routes: {}", fail_count);
-    
-    // Calculate load distribution stats
-    let loads: Vec<u32> = router.accelerators.iter().map(|a| a.current_load).collect();
-    let total_load: u32 = loads.iter().sum();
-    let avg_load = total_load as f64 / num_accelerators as f64;
-    
-    // Variance
-    let variance: f64 = loads.iter()
-        .map(|&load| {
-            let diff = load as f64 - avg_load;
-            diff * diff
-        })
-        .sum::<f64>() / num_accelerators as f64;
-    let std_dev = variance.sqrt();
-
-    println!("Average Load: {:.2}", avg_load);
-    println!("Load Std Dev: {:.2}", std_dev);
+    println!("Time elapsed: {:?}", stats.elapsed);
+    println!("Successful Routes: {}", stats.success_count);
+    println!("Failed Routes: {}", stats.fail_count);
+    println!("Mean Load (per accelerator): {:.2}", stats.mean_load);
+    println!("Load Std Dev: {:.2}", stats.load_std_dev);
+    println!("Load Gini: {:.4}", stats.load_gini);
+    println!("p50 Chosen-Accelerator Load: {:.2}", stats.p50_chosen_load);
+    println!("p99 Chosen-Accelerator Load: {:.2}", stats.p99_chosen_load);
 }
@@ -1,36 +1,213 @@
-use crate::accelerator::Accelerator;
+use crate::accelerator::{Accelerator, CircuitState, DEFAULT_EWMA_TAU};
+use crate::health::{ConsecutiveFailuresHealthChecker, HealthChecker};
 use crate::request::Request;
+use std::sync::{Arc, RwLock};
+use std::time::Instant;
 
 pub trait LoadBalancingStrategy: Send + Sync {
-    fn select_accelerator(&self, accelerators: &[Accelerator], request: &Request) -> Option<usize>;
+    fn select_accelerator(&self, accelerators: &[Arc<Accelerator>], request: &Request) -> Option<usize>;
+
+    /// Ranks up to `n` distinct available accelerators by the strategy's
+    /// notion of cost, for callers that want to hedge or retry against the
+    /// next candidate if the first one times out.
+    ///
+    /// The default repeatedly calls `select_accelerator` over a shrinking
+    /// view with previous picks removed, which is correct for any strategy
+    /// but does `O(n)` selections; strategies that can rank candidates
+    /// directly (`LeastConnections`, `PowerOfTwoChoices`) override this for
+    /// a single pass.
+    fn select_n(&self, accelerators: &[Arc<Accelerator>], request: &Request, n: usize) -> Vec<usize> {
+        if n == 0 || accelerators.is_empty() {
+            return Vec::new();
+        }
+
+        let mut chosen = Vec::with_capacity(n.min(accelerators.len()));
+        let mut remaining: Vec<usize> = (0..accelerators.len()).collect();
+
+        while chosen.len() < n && !remaining.is_empty() {
+            let view: Vec<Arc<Accelerator>> = remaining.iter().map(|&idx| Arc::clone(&accelerators[idx])).collect();
+            match self.select_accelerator(&view, request) {
+                Some(local_idx) => chosen.push(remaining.remove(local_idx)),
+                None => break,
+            }
+        }
+
+        chosen
+    }
+}
+
+/// A RAII handle for load committed by `Router::route_request`.
+///
+/// Holding a `RequestLease` represents an in-flight request; dropping it
+/// (or calling `release` explicitly) returns its committed cost to the
+/// accelerator, so in-flight counts reflect real concurrency instead of
+/// filling up monotonically. It holds its accelerator directly so releasing
+/// load on drop touches only that accelerator's own atomics, with no shared
+/// lock serializing unrelated releases against each other.
+pub struct RequestLease {
+    accelerator: Arc<Accelerator>,
+    cost: u32,
+}
+
+impl RequestLease {
+    pub fn accelerator_id(&self) -> usize {
+        self.accelerator.id
+    }
+
+    pub fn cost(&self) -> u32 {
+        self.cost
+    }
+
+    /// Releases the lease's committed load immediately. Equivalent to
+    /// dropping the lease, but named for call sites that want to make the
+    /// release point explicit.
+    pub fn release(self) {}
 }
 
+impl Drop for RequestLease {
+    fn drop(&mut self) {
+        self.accelerator.remove_load(self.cost);
+    }
+}
+
+/// Routes requests across a pool of accelerators using a pluggable
+/// `LoadBalancingStrategy`.
+///
+/// The hot path (`route_request`) takes `&self` and itself touches only
+/// atomics and an `RwLock` read, so a `Router` can be wrapped in an `Arc` and
+/// routed from many worker threads concurrently. Any further locking comes
+/// from the strategy: a `LoadMetric::PeakEwma` comparison briefly locks each
+/// candidate's RTT mutex, and `WeightedRoundRobin` locks its cached sequence.
 pub struct Router {
-    pub accelerators: Vec<Accelerator>,
+    pub accelerators: Vec<Arc<Accelerator>>,
     strategy: Box<dyn LoadBalancingStrategy>,
+    health_checker: Box<dyn HealthChecker>,
+    /// `Arc`s for the accelerators that are not `Unhealthy`, rebuilt by
+    /// `add_accelerator`, `report_failure`, `report_success` and `tick` (not
+    /// on every route) so `route_request` and
+    /// `route_n` hand the strategy an `O(k)` view of live candidates instead
+    /// of an `O(n)` scan over the whole pool, without paying to reallocate
+    /// this view per request.
+    healthy: RwLock<Vec<Arc<Accelerator>>>,
+    /// Peak-EWMA decay time constant used by `report_rtt`, overridable via
+    /// `with_ewma_tau`.
+    ewma_tau: f64,
 }
 
 impl Router {
     pub fn new(strategy: Box<dyn LoadBalancingStrategy>) -> Self {
+        Self::with_health_checker(strategy, Box::new(ConsecutiveFailuresHealthChecker::default()))
+    }
+
+    pub fn with_health_checker(
+        strategy: Box<dyn LoadBalancingStrategy>,
+        health_checker: Box<dyn HealthChecker>,
+    ) -> Self {
         Self {
             accelerators: Vec::new(),
             strategy,
+            health_checker,
+            healthy: RwLock::new(Vec::new()),
+            ewma_tau: DEFAULT_EWMA_TAU,
         }
     }
 
+    /// Overrides the peak-EWMA decay time constant consulted by `report_rtt`,
+    /// which otherwise defaults to `DEFAULT_EWMA_TAU`.
+    pub fn with_ewma_tau(mut self, tau: f64) -> Self {
+        self.ewma_tau = tau;
+        self
+    }
+
     pub fn add_accelerator(&mut self, accelerator: Accelerator) {
-        self.accelerators.push(accelerator);
+        self.accelerators.push(Arc::new(accelerator));
+        self.rebuild_healthy_index();
     }
 
-    pub fn route_request(&mut self, request: &Request) -> Option<usize> {
-        let idx = self.strategy.select_accelerator(&self.accelerators, request)?;
-        // Ideally we would update load here or return the index for the caller to handle
-        // For simulation purposes, let's assume the router updates the load immediately if successful
-        if let Some(acc) = self.accelerators.get_mut(idx) {
-             if acc.add_load(request.cost).is_ok() {
-                 return Some(acc.id);
-             }
+    /// Reports a failed request against `acc_id`, driving its circuit
+    /// breaker towards `Unhealthy` after enough consecutive failures.
+    pub fn report_failure(&self, acc_id: usize) {
+        let threshold = self.health_checker.failure_threshold();
+        if let Some(acc) = self.accelerators.iter().find(|a| a.id == acc_id) {
+            acc.record_failure(threshold);
+        }
+        self.rebuild_healthy_index();
+    }
+
+    /// Reports a successful request against `acc_id`, closing the circuit
+    /// if it was `Probing`.
+    pub fn report_success(&self, acc_id: usize) {
+        if let Some(acc) = self.accelerators.iter().find(|a| a.id == acc_id) {
+            acc.record_success();
+        }
+        self.rebuild_healthy_index();
+    }
+
+    /// Reports an observed round-trip time for `acc_id`, feeding its
+    /// peak-EWMA estimate so `LoadMetric::PeakEwma`-based strategies actually
+    /// reflect real latency instead of only in-flight count.
+    pub fn report_rtt(&self, acc_id: usize, rtt: f64, now: Instant) {
+        if let Some(acc) = self.accelerators.iter().find(|a| a.id == acc_id) {
+            acc.report_rtt(rtt, self.ewma_tau, now);
+        }
+    }
+
+    /// Drives cooldown-based `Unhealthy` -> `Probing` transitions. Should be
+    /// called periodically (e.g. from a background timer in the sync
+    /// simulation or from the Python bindings).
+    pub fn tick(&self, now: Instant) {
+        let cooldown = self.health_checker.cooldown();
+        for acc in &self.accelerators {
+            acc.maybe_begin_probing(now, cooldown);
+        }
+        self.rebuild_healthy_index();
+    }
+
+    fn rebuild_healthy_index(&self) {
+        let healthy: Vec<Arc<Accelerator>> = self
+            .accelerators
+            .iter()
+            .filter(|acc| acc.circuit_state() != CircuitState::Unhealthy)
+            .map(Arc::clone)
+            .collect();
+        *self.healthy.write().unwrap() = healthy;
+    }
+
+    pub fn route_request(&self, request: &Request) -> Option<RequestLease> {
+        let healthy = self.healthy.read().unwrap();
+        if healthy.is_empty() {
+            return None;
+        }
+
+        let local_idx = self.strategy.select_accelerator(&healthy, request)?;
+
+        let acc = healthy.get(local_idx)?;
+        if acc.add_load(request.cost).is_ok() {
+            return Some(RequestLease {
+                accelerator: Arc::clone(acc),
+                cost: request.cost,
+            });
         }
         None
     }
+
+    /// Ranks up to `n` distinct available accelerator ids for `request`,
+    /// best candidate first, without committing any load. Callers use this
+    /// to hedge a request across the top candidates or retry against the
+    /// next one if the primary times out.
+    pub fn route_n(&self, request: &Request, n: usize) -> Vec<usize> {
+        if n == 0 {
+            return Vec::new();
+        }
+        let healthy = self.healthy.read().unwrap();
+        if healthy.is_empty() {
+            return Vec::new();
+        }
+
+        self.strategy
+            .select_n(&healthy, request, n)
+            .into_iter()
+            .filter_map(|idx| healthy.get(idx).map(|acc| acc.id))
+            .collect()
+    }
 }
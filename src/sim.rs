@@ -0,0 +1,340 @@
+//! A config-driven experiment harness, in the spirit of caminos' modular
+//! simulator: describe a run with a serializable `SimConfig`, replay it
+//! with `run_experiment`, and compare the resulting `SimStats` across
+//! strategies or a swept parameter grid, instead of editing `main.rs` and
+//! recompiling for every scenario.
+
+use crate::accelerator::{Accelerator, LoadMetric};
+use crate::request::Request;
+use crate::router::{LoadBalancingStrategy, Router};
+use crate::strategies::{
+    least_connections::LeastConnections, p2c::PowerOfTwoChoices, round_robin::RoundRobin,
+    weighted_p2c::WeightedP2C, weighted_round_robin::WeightedRoundRobin,
+};
+use rand::Rng;
+use serde::Deserialize;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::sync::atomic::Ordering as AtomicOrdering;
+use std::time::{Duration, Instant};
+
+/// How simulated requests arrive over `SimConfig::duration_secs`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub enum ArrivalProcess {
+    /// Exponentially-distributed inter-arrival times at a fixed average rate.
+    Poisson { rate_per_sec: f64 },
+    /// Evenly-spaced arrivals at a fixed rate.
+    Fixed { rate_per_sec: f64 },
+}
+
+/// How a request's cost is sampled. A request's cost doubles as its hold
+/// time on the accelerator it's routed to, so the simulation can release
+/// load when the request would realistically have completed.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub enum CostDistribution {
+    Uniform { min: u32, max: u32 },
+    Constant { cost: u32 },
+}
+
+impl CostDistribution {
+    fn sample(&self, rng: &mut impl Rng) -> u32 {
+        match *self {
+            CostDistribution::Uniform { min, max } => rng.random_range(min..=max.max(min)),
+            CostDistribution::Constant { cost } => cost,
+        }
+    }
+}
+
+/// Describes one simulation run: the accelerator pool shape, the routing
+/// strategy under test, and the arrival/cost workload to replay against it.
+///
+/// Deserializable from TOML or JSON (see `from_toml`/`from_json`) so
+/// experiments can be swept over a parameter grid without recompiling.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SimConfig {
+    pub num_accelerators: usize,
+    pub accelerator_capacity: u32,
+    #[serde(default = "default_weight")]
+    pub accelerator_weight: f64,
+    /// One of `"round_robin"`, `"least_connections"`, `"p2c"`,
+    /// `"weighted_p2c"`, `"weighted_round_robin"`,
+    /// `"least_connections_peak_ewma"`, `"p2c_peak_ewma"`.
+    pub strategy: String,
+    /// Peak-EWMA decay time constant passed to `Router::with_ewma_tau`, for
+    /// `"*_peak_ewma"` strategies. Defaults to `DEFAULT_EWMA_TAU`.
+    #[serde(default = "default_ewma_tau")]
+    pub ewma_tau: f64,
+    pub arrival: ArrivalProcess,
+    pub cost_distribution: CostDistribution,
+    pub duration_secs: f64,
+}
+
+fn default_weight() -> f64 {
+    1.0
+}
+
+fn default_ewma_tau() -> f64 {
+    crate::accelerator::DEFAULT_EWMA_TAU
+}
+
+impl SimConfig {
+    pub fn from_toml(s: &str) -> Result<Self, String> {
+        toml::from_str(s).map_err(|e| e.to_string())
+    }
+
+    pub fn from_json(s: &str) -> Result<Self, String> {
+        serde_json::from_str(s).map_err(|e| e.to_string())
+    }
+}
+
+/// Structured output of `run_experiment`, suitable for comparing strategies
+/// or sweeping `SimConfig` parameter grids.
+#[derive(Debug, Clone)]
+pub struct SimStats {
+    pub success_count: u64,
+    pub fail_count: u64,
+    /// Mean total cost served per accelerator over the run.
+    pub mean_load: f64,
+    pub load_std_dev: f64,
+    /// Gini coefficient of total cost served per accelerator (0 = perfectly
+    /// even, 1 = maximally unequal).
+    pub load_gini: f64,
+    /// p50 of the chosen accelerator's in-flight load at the moment each
+    /// successful request was routed.
+    pub p50_chosen_load: f64,
+    /// p99 of the chosen accelerator's in-flight load at the moment each
+    /// successful request was routed.
+    pub p99_chosen_load: f64,
+    pub elapsed: Duration,
+}
+
+fn build_strategy(name: &str) -> Result<Box<dyn LoadBalancingStrategy>, String> {
+    Ok(match name {
+        "round_robin" => Box::new(RoundRobin::new()),
+        "least_connections" => Box::new(LeastConnections::new()),
+        "p2c" => Box::new(PowerOfTwoChoices::new()),
+        "weighted_p2c" => Box::new(WeightedP2C::new()),
+        "weighted_round_robin" => Box::new(WeightedRoundRobin::new()),
+        "least_connections_peak_ewma" => Box::new(LeastConnections::with_metric(LoadMetric::PeakEwma)),
+        "p2c_peak_ewma" => Box::new(PowerOfTwoChoices::with_metric(LoadMetric::PeakEwma)),
+        other => return Err(format!("unknown strategy: {other}")),
+    })
+}
+
+/// Generates arrival timestamps (in seconds, from the start of the run)
+/// under `arrival`, stopping once they reach `duration_secs`.
+fn generate_arrivals(arrival: &ArrivalProcess, duration_secs: f64, rng: &mut impl Rng) -> Vec<f64> {
+    let mut times = Vec::new();
+    let mut t = 0.0;
+    match *arrival {
+        ArrivalProcess::Poisson { rate_per_sec } => {
+            let rate = rate_per_sec.max(f64::EPSILON);
+            loop {
+                let u: f64 = rng.random_range(f64::EPSILON..1.0);
+                t -= u.ln() / rate;
+                if t >= duration_secs {
+                    break;
+                }
+                times.push(t);
+            }
+        }
+        ArrivalProcess::Fixed { rate_per_sec } => {
+            let interval = 1.0 / rate_per_sec.max(f64::EPSILON);
+            while t < duration_secs {
+                times.push(t);
+                t += interval;
+            }
+        }
+    }
+    times
+}
+
+/// A request's scheduled completion, ordered by `time` so a `BinaryHeap`
+/// of these acts as a min-heap over the next accelerator to free up.
+struct Departure {
+    time: f64,
+    acc_id: usize,
+    /// The request's cost, doubling as its observed RTT once it departs
+    /// (see `CostDistribution`'s doc comment).
+    cost: u32,
+    // Held purely so dropping it (via `Drop`) releases its committed load;
+    // never read directly.
+    _lease: crate::router::RequestLease,
+}
+
+impl PartialEq for Departure {
+    fn eq(&self, other: &Self) -> bool {
+        self.time == other.time
+    }
+}
+
+impl Eq for Departure {}
+
+impl PartialOrd for Departure {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Departure {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.time.partial_cmp(&self.time).unwrap()
+    }
+}
+
+/// Builds a fresh `Router` from `config`, replays its arrival process for
+/// `config.duration_secs`, and returns structured metrics over the run.
+///
+/// Each successful request's load is released once its sampled cost (used
+/// as a hold time) has elapsed, so load reflects real queueing rather than
+/// accumulating monotonically.
+pub fn run_experiment(config: &SimConfig) -> Result<SimStats, String> {
+    let strategy = build_strategy(&config.strategy)?;
+    let mut router = Router::new(strategy).with_ewma_tau(config.ewma_tau);
+    for i in 0..config.num_accelerators {
+        let acc = Accelerator::new(i, config.accelerator_capacity).with_weight(config.accelerator_weight);
+        router.add_accelerator(acc);
+    }
+
+    let mut rng = rand::thread_rng();
+    let start_time = Instant::now();
+
+    let arrivals = generate_arrivals(&config.arrival, config.duration_secs, &mut rng);
+    let mut departures: BinaryHeap<Departure> = BinaryHeap::new();
+
+    let mut success_count = 0u64;
+    let mut fail_count = 0u64;
+    let mut served = vec![0.0f64; config.num_accelerators];
+    let mut chosen_loads = Vec::new();
+
+    for (i, &arrival_time) in arrivals.iter().enumerate() {
+        while let Some(departure) = departures.peek() {
+            if departure.time > arrival_time {
+                break;
+            }
+            let departure = departures.pop().unwrap();
+            let departed_at = start_time + Duration::from_secs_f64(departure.time);
+            router.report_rtt(departure.acc_id, departure.cost as f64, departed_at);
+        }
+
+        let cost = config.cost_distribution.sample(&mut rng);
+        let request = Request::new(i, cost, 1);
+        match router.route_request(&request) {
+            Some(lease) => {
+                success_count += 1;
+                let acc_id = lease.accelerator_id();
+                served[acc_id] += cost as f64;
+                chosen_loads.push(router.accelerators[acc_id].current_load.load(AtomicOrdering::Relaxed) as f64);
+                departures.push(Departure {
+                    time: arrival_time + cost as f64,
+                    acc_id,
+                    cost,
+                    _lease: lease,
+                });
+            }
+            None => fail_count += 1,
+        }
+    }
+
+    let n = served.len().max(1) as f64;
+    let mean_load = served.iter().sum::<f64>() / n;
+    let variance = served.iter().map(|&s| (s - mean_load).powi(2)).sum::<f64>() / n;
+    let load_std_dev = variance.sqrt();
+    let load_gini = gini_coefficient(&served);
+
+    chosen_loads.sort_by(|a: &f64, b: &f64| a.partial_cmp(b).unwrap());
+    let p50_chosen_load = percentile(&chosen_loads, 0.50);
+    let p99_chosen_load = percentile(&chosen_loads, 0.99);
+
+    Ok(SimStats {
+        success_count,
+        fail_count,
+        mean_load,
+        load_std_dev,
+        load_gini,
+        p50_chosen_load,
+        p99_chosen_load,
+        elapsed: start_time.elapsed(),
+    })
+}
+
+/// Nearest-rank percentile over an already-sorted slice.
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = (p * (sorted.len() - 1) as f64).round() as usize;
+    sorted[rank.min(sorted.len() - 1)]
+}
+
+/// Gini coefficient of `values` (0 = perfectly even, 1 = maximally unequal).
+fn gini_coefficient(values: &[f64]) -> f64 {
+    let n = values.len();
+    if n == 0 {
+        return 0.0;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let sum: f64 = sorted.iter().sum();
+    if sum == 0.0 {
+        return 0.0;
+    }
+    let weighted_cumulative: f64 = sorted.iter().enumerate().map(|(i, &v)| (i as f64 + 1.0) * v).sum();
+    (2.0 * weighted_cumulative) / (n as f64 * sum) - (n as f64 + 1.0) / n as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentile_picks_nearest_rank() {
+        let sorted = [1.0, 2.0, 3.0, 4.0, 5.0];
+        assert_eq!(percentile(&sorted, 0.0), 1.0);
+        assert_eq!(percentile(&sorted, 0.5), 3.0);
+        assert_eq!(percentile(&sorted, 1.0), 5.0);
+    }
+
+    #[test]
+    fn percentile_of_empty_is_zero() {
+        assert_eq!(percentile(&[], 0.5), 0.0);
+    }
+
+    #[test]
+    fn gini_coefficient_of_equal_values_is_zero() {
+        let values = [10.0, 10.0, 10.0, 10.0];
+        assert!(gini_coefficient(&values).abs() < 1e-9);
+    }
+
+    #[test]
+    fn gini_coefficient_of_maximally_unequal_values_approaches_one() {
+        let mut values = vec![0.0; 99];
+        values.push(100.0);
+        let gini = gini_coefficient(&values);
+        assert!(gini > 0.9, "expected near-maximal inequality, got {gini}");
+    }
+
+    #[test]
+    fn gini_coefficient_of_empty_or_all_zero_is_zero() {
+        assert_eq!(gini_coefficient(&[]), 0.0);
+        assert_eq!(gini_coefficient(&[0.0, 0.0, 0.0]), 0.0);
+    }
+
+    #[test]
+    fn peak_ewma_run_with_default_tau_has_no_failures() {
+        let config = SimConfig {
+            num_accelerators: 4,
+            accelerator_capacity: 10,
+            accelerator_weight: 1.0,
+            strategy: "p2c_peak_ewma".to_string(),
+            ewma_tau: default_ewma_tau(),
+            arrival: ArrivalProcess::Fixed { rate_per_sec: 200.0 },
+            cost_distribution: CostDistribution::Constant { cost: 1 },
+            duration_secs: 0.05,
+        };
+        let stats = run_experiment(&config).expect("valid config");
+        assert_eq!(stats.fail_count, 0);
+    }
+}
@@ -1,22 +1,56 @@
-use crate::accelerator::Accelerator;
+use crate::accelerator::{Accelerator, LoadMetric};
 use crate::request::Request;
 use crate::router::LoadBalancingStrategy;
+use std::sync::Arc;
 
-pub struct LeastConnections;
+pub struct LeastConnections {
+    metric: LoadMetric,
+}
 
 impl LeastConnections {
     pub fn new() -> Self {
-        Self
+        Self {
+            metric: LoadMetric::InFlight,
+        }
+    }
+
+    /// Builds a `LeastConnections` that ranks accelerators by `metric`
+    /// instead of the default in-flight count, e.g. `LoadMetric::PeakEwma`
+    /// to route around slow accelerators rather than just busy ones.
+    pub fn with_metric(metric: LoadMetric) -> Self {
+        Self { metric }
     }
 }
 
 impl LoadBalancingStrategy for LeastConnections {
-    fn select_accelerator(&self, accelerators: &[Accelerator], _request: &Request) -> Option<usize> {
+    fn select_accelerator(&self, accelerators: &[Arc<Accelerator>], _request: &Request) -> Option<usize> {
         accelerators
             .iter()
             .enumerate()
             .filter(|(_, acc)| acc.is_available())
-            .min_by_key(|(_, acc)| acc.current_load)
+            .min_by(|(_, a), (_, b)| a.load(self.metric).total_cmp(&b.load(self.metric)))
             .map(|(idx, _)| idx)
     }
+
+    /// Partial-sorts the available accelerators by `metric` instead of
+    /// ranking `n` times via `select_accelerator`, so hedging against a
+    /// large pool stays cheap.
+    fn select_n(&self, accelerators: &[Arc<Accelerator>], _request: &Request, n: usize) -> Vec<usize> {
+        let mut available: Vec<(usize, f64)> = accelerators
+            .iter()
+            .enumerate()
+            .filter(|(_, acc)| acc.is_available())
+            .map(|(idx, acc)| (idx, acc.load(self.metric)))
+            .collect();
+
+        let n = n.min(available.len());
+        if n == 0 {
+            return Vec::new();
+        }
+
+        available.select_nth_unstable_by(n - 1, |a, b| a.1.total_cmp(&b.1));
+        available.truncate(n);
+        available.sort_by(|a, b| a.1.total_cmp(&b.1));
+        available.into_iter().map(|(idx, _)| idx).collect()
+    }
 }
@@ -0,0 +1,5 @@
+pub mod least_connections;
+pub mod p2c;
+pub mod round_robin;
+pub mod weighted_p2c;
+pub mod weighted_round_robin;
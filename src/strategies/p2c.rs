@@ -1,32 +1,44 @@
-use crate::accelerator::Accelerator;
+use crate::accelerator::{Accelerator, LoadMetric};
 use crate::request::Request;
 use crate::router::LoadBalancingStrategy;
 use rand::Rng;
+use std::sync::Arc;
 
-pub struct PowerOfTwoChoices;
+pub struct PowerOfTwoChoices {
+    metric: LoadMetric,
+}
 
 impl PowerOfTwoChoices {
     pub fn new() -> Self {
-        Self
+        Self {
+            metric: LoadMetric::InFlight,
+        }
+    }
+
+    /// Builds a `PowerOfTwoChoices` that ranks accelerators by `metric`
+    /// instead of the default in-flight count, e.g. `LoadMetric::PeakEwma`
+    /// to route around slow accelerators rather than just busy ones.
+    pub fn with_metric(metric: LoadMetric) -> Self {
+        Self { metric }
     }
 }
 
 impl LoadBalancingStrategy for PowerOfTwoChoices {
-    fn select_accelerator(&self, accelerators: &[Accelerator], _request: &Request) -> Option<usize> {
+    fn select_accelerator(&self, accelerators: &[Arc<Accelerator>], _request: &Request) -> Option<usize> {
         if accelerators.is_empty() {
             return None;
         }
-        
+
         let mut rng = rand::thread_rng();
         let len = accelerators.len();
-        
+
         // Pick two random indices
         let idx1 = rng.random_range(0..len);
         let idx2 = rng.random_range(0..len);
-        
+
         let acc1 = &accelerators[idx1];
         let acc2 = &accelerators[idx2];
-        
+
         // If one is unavailable, pick the other if available
         if !acc1.is_available() {
             return if acc2.is_available() { Some(idx2) } else { None };
@@ -34,12 +46,38 @@ impl LoadBalancingStrategy for PowerOfTwoChoices {
         if !acc2.is_available() {
             return Some(idx1);
         }
-        
+
         // Both available, pick the one with less load
-        if acc1.current_load <= acc2.current_load {
+        if acc1.load(self.metric) <= acc2.load(self.metric) {
             Some(idx1)
         } else {
             Some(idx2)
         }
     }
+
+    /// Samples `2n` candidate indices, capped at the pool size (with repeats, mirroring the `idx1`/
+    /// `idx2` sampling in `select_accelerator`) and keeps the `n`
+    /// least-loaded available ones, rather than running the pairwise
+    /// tournament `n` times.
+    fn select_n(&self, accelerators: &[Arc<Accelerator>], _request: &Request, n: usize) -> Vec<usize> {
+        if accelerators.is_empty() || n == 0 {
+            return Vec::new();
+        }
+
+        let len = accelerators.len();
+        let sample_size = (2 * n).min(len);
+        let mut rng = rand::thread_rng();
+        let mut sampled: Vec<usize> = (0..sample_size).map(|_| rng.random_range(0..len)).collect();
+        sampled.sort_unstable();
+        sampled.dedup();
+
+        let mut candidates: Vec<(usize, f64)> = sampled
+            .into_iter()
+            .filter(|&idx| accelerators[idx].is_available())
+            .map(|idx| (idx, accelerators[idx].load(self.metric)))
+            .collect();
+        candidates.sort_by(|a, b| a.1.total_cmp(&b.1));
+        candidates.truncate(n);
+        candidates.into_iter().map(|(idx, _)| idx).collect()
+    }
 }
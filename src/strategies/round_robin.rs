@@ -2,6 +2,7 @@ use crate::accelerator::Accelerator;
 use crate::request::Request;
 use crate::router::LoadBalancingStrategy;
 use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 
 pub struct RoundRobin {
     current_index: AtomicUsize,
@@ -16,7 +17,7 @@ impl RoundRobin {
 }
 
 impl LoadBalancingStrategy for RoundRobin {
-    fn select_accelerator(&self, accelerators: &[Accelerator], _request: &Request) -> Option<usize> {
+    fn select_accelerator(&self, accelerators: &[Arc<Accelerator>], _request: &Request) -> Option<usize> {
         if accelerators.is_empty() {
             return None;
         }
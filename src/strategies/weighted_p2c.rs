@@ -0,0 +1,56 @@
+use crate::accelerator::{Accelerator, LoadMetric};
+use crate::request::Request;
+use crate::router::LoadBalancingStrategy;
+use rand::Rng;
+use std::sync::Arc;
+
+/// Power-of-two-choices variant that accounts for per-accelerator `weight`.
+///
+/// Candidates are ranked by *effective load* = `current_load / weight`
+/// instead of raw `current_load`, so operators can red-line test new
+/// hardware or roll it out blue-green by tuning `weight` without touching
+/// the `Router`.
+pub struct WeightedP2C;
+
+impl WeightedP2C {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+fn effective_load(acc: &Accelerator) -> f64 {
+    acc.load(LoadMetric::InFlight) / acc.weight.max(f64::EPSILON)
+}
+
+impl LoadBalancingStrategy for WeightedP2C {
+    fn select_accelerator(&self, accelerators: &[Arc<Accelerator>], _request: &Request) -> Option<usize> {
+        if accelerators.is_empty() {
+            return None;
+        }
+
+        let mut rng = rand::thread_rng();
+        let len = accelerators.len();
+
+        // Pick two random indices
+        let idx1 = rng.random_range(0..len);
+        let idx2 = rng.random_range(0..len);
+
+        let acc1 = &accelerators[idx1];
+        let acc2 = &accelerators[idx2];
+
+        // If one is unavailable, pick the other if available
+        if !acc1.is_available() {
+            return if acc2.is_available() { Some(idx2) } else { None };
+        }
+        if !acc2.is_available() {
+            return Some(idx1);
+        }
+
+        // Both available, pick the one with less effective (weight-adjusted) load
+        if effective_load(acc1) <= effective_load(acc2) {
+            Some(idx1)
+        } else {
+            Some(idx2)
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use crate::accelerator::Accelerator;
+use crate::request::Request;
+use crate::router::LoadBalancingStrategy;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// The expanded weighted sequence, cached against the ids of the
+/// accelerators it was built from, so any change to the view it is given (a
+/// pool resize, or an accelerator leaving or rejoining the router's healthy
+/// view) invalidates it without needing a per-request rebuild.
+struct CachedSequence {
+    accelerator_ids: Vec<usize>,
+    sequence: Vec<usize>,
+}
+
+/// Round-robin variant where each accelerator receives a share of requests
+/// proportional to its `weight`, rounded to the nearest integer.
+///
+/// Indices are expanded into a repeating sequence (an accelerator with
+/// weight `3.0` appears three times per cycle) which is then walked the
+/// same way `RoundRobin` walks the plain index list, so this lets users
+/// gradually shift load onto new hardware without code changes to the
+/// `Router`. The expanded sequence is cached rather than rebuilt on every
+/// `select_accelerator` call, since it only changes when the set of
+/// accelerators passed in does.
+pub struct WeightedRoundRobin {
+    current_index: AtomicUsize,
+    cached: Mutex<CachedSequence>,
+}
+
+impl WeightedRoundRobin {
+    pub fn new() -> Self {
+        Self {
+            current_index: AtomicUsize::new(0),
+            cached: Mutex::new(CachedSequence {
+                accelerator_ids: Vec::new(),
+                sequence: Vec::new(),
+            }),
+        }
+    }
+
+    fn expanded_sequence(accelerators: &[Arc<Accelerator>]) -> Vec<usize> {
+        let mut sequence = Vec::new();
+        for (idx, acc) in accelerators.iter().enumerate() {
+            let copies = acc.weight.max(0.0).round() as usize;
+            sequence.extend(std::iter::repeat_n(idx, copies));
+        }
+        sequence
+    }
+}
+
+impl LoadBalancingStrategy for WeightedRoundRobin {
+    fn select_accelerator(&self, accelerators: &[Arc<Accelerator>], _request: &Request) -> Option<usize> {
+        if accelerators.is_empty() {
+            return None;
+        }
+
+        let mut cached = self.cached.lock().unwrap();
+        if !cached.accelerator_ids.iter().copied().eq(accelerators.iter().map(|acc| acc.id)) {
+            cached.sequence = Self::expanded_sequence(accelerators);
+            cached.accelerator_ids = accelerators.iter().map(|acc| acc.id).collect();
+        }
+        let sequence = &cached.sequence;
+        if sequence.is_empty() {
+            return None;
+        }
+
+        let start = self.current_index.fetch_add(1, Ordering::Relaxed) % sequence.len();
+
+        // Walk the weighted sequence looking for the next available accelerator
+        for offset in 0..sequence.len() {
+            let idx = sequence[(start + offset) % sequence.len()];
+            if accelerators[idx].is_available() {
+                return Some(idx);
+            }
+        }
+        None
+    }
+}